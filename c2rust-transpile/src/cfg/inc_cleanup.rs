@@ -1,72 +1,157 @@
 //! This module provides support for removing the extraneous break statements
-//! generated by the incremental relooper.
+//! generated by the incremental relooper, plus a handful of cosmetic
+//! simplifications (needless-return collapsing, redundant-else flattening,
+//! unused-brace flattening) that clean up after it.
 use super::*;
 
+/// A single post-relooper simplification pass. Mutates `stmts` in place and
+/// reports whether it changed anything, so `IncCleanup::remove_tail_expr`
+/// can run the enabled passes to a fixpoint.
+pub trait StmtSimplify {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool;
+}
+
+/// Which of `IncCleanup`'s passes to run. Everything is on by default;
+/// downstream translation options can disable the more aggressive
+/// rewrites (`needless_return`, `redundant_else`) independently.
+#[derive(Copy, Clone, Debug)]
+pub struct CleanupConfig {
+    pub idempotent_tail: bool,
+    pub needless_return: bool,
+    pub redundant_else: bool,
+    pub empty_else: bool,
+    pub unused_braces: bool,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        CleanupConfig {
+            idempotent_tail: true,
+            needless_return: true,
+            redundant_else: true,
+            empty_else: true,
+            unused_braces: true,
+        }
+    }
+}
+
 pub struct IncCleanup {
-    in_tail: Option<ImplicitReturnType>,
-    brk_lbl: Label,
+    // Kept separate from `passes` (rather than just another `StmtSimplify`
+    // in the list) because `remove_tail_expr`'s return value must reflect
+    // *only* what this one pass did, not the cosmetic passes.
+    idempotent_tail: Option<IdempotentTail>,
+    // Cosmetic passes, in an order later passes depend on: `NeedlessReturn`
+    // must run (to a local fixpoint, within a single `apply`) before
+    // `RedundantElse` ever sees the tree, since `RedundantElse` treats any
+    // trailing `return` as diverging control flow. If it ran first on a
+    // still-`return`-shaped `if c { return a; } else { return b; }` in a
+    // used-tail position, it would splice `b` out of the `else` and turn it
+    // into a statement, losing the tail value `b` was supposed to produce.
+    // Do not reorder `passes` without re-checking this.
+    passes: Vec<Box<dyn StmtSimplify>>,
 }
 
 impl IncCleanup {
+    /// Run every pass with the default `CleanupConfig`. Use `with_config`
+    /// to pick a non-default set of passes.
     pub fn new(in_tail: Option<ImplicitReturnType>, brk_lbl: Label) -> Self {
-        IncCleanup{in_tail, brk_lbl}
+        Self::with_config(in_tail, brk_lbl, CleanupConfig::default())
     }
 
-    /// The only way we can say for sure that we don't need a labelled block is if we remove
-    /// the (unique) break to that label. We know that the label will be unique because relooper
-    /// never duplicates blocks.
+    pub fn with_config(in_tail: Option<ImplicitReturnType>, brk_lbl: Label, config: CleanupConfig) -> Self {
+        let idempotent_tail = if config.idempotent_tail {
+            Some(IdempotentTail { in_tail, brk_lbl: brk_lbl.clone() })
+        } else {
+            None
+        };
+
+        let mut passes: Vec<Box<dyn StmtSimplify>> = Vec::new();
+        if config.needless_return {
+            passes.push(Box::new(NeedlessReturn { in_tail }));
+        }
+        if config.redundant_else {
+            passes.push(Box::new(RedundantElse));
+        }
+        if config.empty_else {
+            passes.push(Box::new(EmptyElse));
+        }
+        if config.unused_braces {
+            passes.push(Box::new(UnusedBraces));
+        }
+        IncCleanup { idempotent_tail, passes }
+    }
+
+    /// Run every enabled pass over `stmts` to a fixpoint: loop until a full
+    /// round leaves nothing changed, since e.g. `UnusedBraces` emptying a
+    /// block can expose a new tail `return` on the next round.
     ///
-    /// Returns true if we manage to remove a tail expr.
+    /// The only way we can say for sure that we don't need a labelled block
+    /// is if we remove the (unique) break to that label, so — unlike the
+    /// cosmetic passes, which are free to fire without saying anything
+    /// about the label — the return value reflects only whether
+    /// `IdempotentTail` ever removed it (relooper never duplicates blocks,
+    /// so the label is unique if it exists at all).
     pub fn remove_tail_expr(&self, stmts: &mut Vec<Stmt>) -> bool {
-        if let Some(mut stmt) = stmts.pop() {
-            // If the very last stmt in our relooped output is a return/break, we can just
-            // remove that statement. We additionally know that there is definitely no need
-            // to label a block (if we were in that mode in the first place).
-            if self.is_idempotent_tail_expr(&stmt) {
-                return true;
+        let mut removed_tail_break = false;
+        loop {
+            let mut changed = false;
+            if let Some(ref idempotent_tail) = self.idempotent_tail {
+                if idempotent_tail.apply(stmts) {
+                    changed = true;
+                    removed_tail_break = true;
+                }
             }
+            for pass in &self.passes {
+                changed |= pass.apply(stmts);
+            }
+            if !changed {
+                break;
+            }
+        }
+        removed_tail_break
+    }
+}
 
-            let mut removed_tail_expr = false;
-
-            if let StmtKind::Expr(ref mut expr) = stmt.node {
-                match expr.node {
-                    ExprKind::If(_, ref mut body, ref mut sels) => {
-                        removed_tail_expr = removed_tail_expr || self.remove_tail_expr(&mut body.stmts);
-                        if let Some(els) = sels {
-                            if let ExprKind::Block(ref mut blk, _) = els.node {
-                                removed_tail_expr = removed_tail_expr || self.remove_tail_expr(&mut blk.stmts)
-                            }
+/// Recurse into the same tail positions `IdempotentTail`/`NeedlessReturn`/
+/// `EmptyElse` all descend into: the last statement of `stmts` itself, and
+/// from there the last statement of an `if`'s then/else blocks or of every
+/// `match` arm's block.
+macro_rules! recurse_tail {
+    ($self_:expr, $stmt:expr, $changed:expr) => {
+        if let StmtKind::Expr(ref mut expr) = $stmt.node {
+            match expr.node {
+                ExprKind::If(_, ref mut body, ref mut sels) => {
+                    $changed |= $self_.apply(&mut body.stmts);
+                    if let Some(els) = sels {
+                        if let ExprKind::Block(ref mut blk, _) = els.node {
+                            $changed |= $self_.apply(&mut blk.stmts);
                         }
                     }
-
-                    ExprKind::Match(_, ref mut cases) => {
-                        // Block label can be removed from any arm
-                        for case in cases {
-                            match case.body.node {
-                                ExprKind::Block(ref mut blk, _) => {
-                                    removed_tail_expr = removed_tail_expr || self.remove_tail_expr(&mut blk.stmts)
-                                }
-                                _ => (),
-                            }
+                }
+                ExprKind::Match(_, ref mut cases) => {
+                    for case in cases {
+                        if let ExprKind::Block(ref mut blk, _) = case.body.node {
+                            $changed |= $self_.apply(&mut blk.stmts);
                         }
                     }
-
-                    _ => (),
                 }
+                _ => (),
             }
-
-            stmt = cleanup_if(stmt);
-
-            // In all other cases, we give up and accept that we can't get rid of the last
-            // stmt and that we might need a block label.
-            stmts.push(stmt);
-            removed_tail_expr
-        } else {
-            false
         }
-    }
+    };
+}
 
-    fn is_idempotent_tail_expr(&self, stmt: &Stmt) -> bool {
+/// If the very last stmt in our relooped output is a `return`/`break` we
+/// know is a no-op here, drop it outright. We additionally know that there
+/// is definitely no need to label a block (if we were in that mode in the
+/// first place).
+struct IdempotentTail {
+    in_tail: Option<ImplicitReturnType>,
+    brk_lbl: Label,
+}
+
+impl IdempotentTail {
+    fn is_idempotent(&self, stmt: &Stmt) -> bool {
         let tail_expr = if let Stmt { node: StmtKind::Semi(ref expr), .. } = *stmt {
             expr
         } else {
@@ -104,25 +189,427 @@ impl IncCleanup {
     }
 }
 
+impl StmtSimplify for IdempotentTail {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool {
+        let mut stmt = match stmts.pop() {
+            Some(stmt) => stmt,
+            None => return false,
+        };
+
+        if self.is_idempotent(&stmt) {
+            return true;
+        }
+
+        let mut changed = false;
+        recurse_tail!(self, stmt, changed);
+        stmts.push(stmt);
+        changed
+    }
+}
+
+/// Collapse a trailing `return <expr>;` into a tail expression `<expr>`
+/// (clippy's `needless_return`). Only fires for `ImplicitReturnType::StmtExpr`,
+/// the variant that means the block's value is actually consumed as a
+/// statement-expression; every other variant (`Main`/`Void` are
+/// `IdempotentTail`'s job, `None` is break-label mode, and anything else we
+/// don't specifically know is a used-value position) is left untouched
+/// rather than assumed safe to rewrite.
+struct NeedlessReturn {
+    in_tail: Option<ImplicitReturnType>,
+}
+
+impl NeedlessReturn {
+    fn collapse(&self, stmt: Stmt) -> (Stmt, bool) {
+        let is_used_tail = matches!(self.in_tail, Some(ImplicitReturnType::StmtExpr(..)));
+        if is_used_tail {
+            if let Stmt { node: StmtKind::Semi(ref expr), .. } = stmt {
+                if let Expr { node: ExprKind::Ret(Some(ref ret_expr)), .. } = **expr {
+                    let ret_expr = ret_expr.clone();
+                    return (Stmt { node: StmtKind::Expr(ret_expr), ..stmt }, true);
+                }
+            }
+        }
+        (stmt, false)
+    }
+}
+
+impl StmtSimplify for NeedlessReturn {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool {
+        let stmt = match stmts.pop() {
+            Some(stmt) => stmt,
+            None => return false,
+        };
+
+        let (mut stmt, mut changed) = self.collapse(stmt);
+        recurse_tail!(self, stmt, changed);
+        stmts.push(stmt);
+        changed
+    }
+}
+
 /// Remove empty else clauses from if expressions that can arise from
 /// removing idempotent statements.
-fn cleanup_if(stmt: Stmt) -> Stmt {
+struct EmptyElse;
+
+impl StmtSimplify for EmptyElse {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool {
+        let mut stmt = match stmts.pop() {
+            Some(stmt) => stmt,
+            None => return false,
+        };
+
+        let mut changed = false;
+        recurse_tail!(self, stmt, changed);
+
+        let (stmt, emptied) = cleanup_if(stmt);
+        changed |= emptied;
+        stmts.push(stmt);
+        changed
+    }
+}
+
+fn cleanup_if(stmt: Stmt) -> (Stmt, bool) {
     if let Stmt { node: StmtKind::Expr(ref expr), .. } = &stmt {
         if let Expr { node: ExprKind::If(ref cond, ref body, ref els), .. } = **expr {
             if let Some(ref els) = els {
                 if let Expr { node: ExprKind::Block(ref blk, None), .. } = **els {
                     if blk.stmts.is_empty() {
-                        return Stmt {
+                        let new_stmt = Stmt {
                             node: StmtKind::Expr(P(Expr {
                                 node: ExprKind::If(cond.clone(), body.clone(), None),
                                 ..(**expr).clone()
                             })),
-                            ..stmt
+                            ..stmt.clone()
+                        };
+                        return (new_stmt, true);
+                    }
+                }
+            }
+        }
+    }
+    (stmt, false)
+}
+
+/// When the then-branch of an `if` unconditionally diverges (clippy's
+/// `REDUNDANT_ELSE`), drop the `else` and splice its statements into the
+/// enclosing block right after the `if`:
+/// `if c { return x; } else { rest }` -> `if c { return x; } rest`.
+///
+/// Unlike the other passes here this isn't confined to tail position — a
+/// diverging `if` can show up anywhere in a block — so it walks every
+/// statement instead of only the last one.
+struct RedundantElse;
+
+impl StmtSimplify for RedundantElse {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool {
+        let mut changed = false;
+        let mut i = 0;
+        while i < stmts.len() {
+            if flatten_redundant_else(stmts, i) {
+                changed = true;
+                // Re-examine the same index: the `if` there now has no
+                // `else`, so this time it falls through to the recursion
+                // into its body below; the spliced-in statements follow it.
+                continue;
+            }
+
+            if let StmtKind::Expr(ref mut expr) | StmtKind::Semi(ref mut expr) = stmts[i].node {
+                match expr.node {
+                    ExprKind::If(_, ref mut body, ref mut els) => {
+                        changed |= self.apply(&mut body.stmts);
+                        if let Some(ref mut els) = els {
+                            changed |= self.recurse_into_else(els);
+                        }
+                    }
+                    ExprKind::Match(_, ref mut cases) => {
+                        for case in cases {
+                            if let ExprKind::Block(ref mut blk, None) = case.body.node {
+                                changed |= self.apply(&mut blk.stmts);
+                            }
                         }
                     }
+                    _ => (),
+                }
+            }
+            i += 1;
+        }
+        changed
+    }
+}
+
+impl RedundantElse {
+    /// Descend into an `else` arm: a plain `{ .. }` block is just another
+    /// statement list, but an `else if ..` is a nested `If` expression
+    /// rather than a `Stmt` in a `Vec`, so `flatten_redundant_else`'s
+    /// splice-by-index approach doesn't apply to it directly — instead walk
+    /// its body (and, transitively, its own `else`) the same way.
+    fn recurse_into_else(&self, els: &mut P<Expr>) -> bool {
+        match els.node {
+            ExprKind::Block(ref mut blk, None) => self.apply(&mut blk.stmts),
+            ExprKind::If(_, ref mut body, ref mut nested_els) => {
+                let mut changed = self.apply(&mut body.stmts);
+                if let Some(ref mut nested_els) = nested_els {
+                    changed |= self.recurse_into_else(nested_els);
                 }
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Only unwraps `stmts[idx]`'s own `else`: a plain block's statements, or an
+/// `else if` whose own then-branch diverges too, spliced in whole as the next
+/// statement. Any other `else if` chain is handled by
+/// `RedundantElse::recurse_into_else` walking into the nested `if`'s body
+/// instead, since there's no statement list to splice into at that level.
+fn flatten_redundant_else(stmts: &mut Vec<Stmt>, idx: usize) -> bool {
+    let spliced = {
+        let stmt = &stmts[idx];
+        let expr = match stmt.node {
+            StmtKind::Expr(ref expr) | StmtKind::Semi(ref expr) => expr,
+            _ => return false,
+        };
+        let (cond, body, els) = match expr.node {
+            ExprKind::If(ref cond, ref body, Some(ref els)) => (cond, body, els),
+            _ => return false,
+        };
+        if !diverges(&body.stmts) {
+            return false;
+        }
+        match els.node {
+            // `unsafe`/`const`/`async`/`try` blocks are also
+            // `Block(_, None)` — splicing their statements out into the
+            // enclosing block would silently drop that context, so only
+            // unwrap a plain block.
+            ExprKind::Block(ref blk, None) if blk.rules == BlockCheckMode::Default => {
+                let new_if = Stmt {
+                    node: StmtKind::Expr(P(Expr {
+                        node: ExprKind::If(cond.clone(), body.clone(), None),
+                        ..(**expr).clone()
+                    })),
+                    ..stmt.clone()
+                };
+                (new_if, blk.stmts.clone())
             }
+            // A diverging `else if ..` is spliced out whole as the next
+            // statement, where its own `else` is unwrapped in turn.
+            ExprKind::If(_, ref nested_body, _) if diverges(&nested_body.stmts) => {
+                let new_if = Stmt {
+                    node: StmtKind::Expr(P(Expr {
+                        node: ExprKind::If(cond.clone(), body.clone(), None),
+                        ..(**expr).clone()
+                    })),
+                    ..stmt.clone()
+                };
+                let node = if let StmtKind::Semi(_) = stmt.node { StmtKind::Semi(els.clone()) } else { StmtKind::Expr(els.clone()) };
+                (new_if, vec![Stmt { node, ..stmt.clone() }])
+            }
+            // Any other `else if ...`, an `unsafe`/etc. else-block, or a
+            // labelled `else` block: leave the chain intact.
+            _ => return false,
+        }
+    };
+
+    let (new_if, els_stmts) = spliced;
+    stmts[idx] = new_if;
+    for (offset, s) in els_stmts.into_iter().enumerate() {
+        stmts.insert(idx + 1 + offset, s);
+    }
+    true
+}
+
+/// True if the last statement of `stmts` unconditionally transfers control
+/// out of the block, i.e. anything spliced in right after it (such as a
+/// redundant `else`'s statements) would be unreachable through it.
+fn diverges(stmts: &[Stmt]) -> bool {
+    let expr = match stmts.last() {
+        Some(Stmt { node: StmtKind::Semi(ref expr), .. }) => expr,
+        Some(Stmt { node: StmtKind::Expr(ref expr), .. }) => expr,
+        _ => return false,
+    };
+    expr_diverges(expr)
+}
+
+/// True if `expr` unconditionally transfers control out of its enclosing
+/// block. Shared between `diverges` (looking at the last statement of a
+/// block) and its own recursion into an `if`/`else if` chain, which
+/// diverges as a whole only if every arm does — including a final plain
+/// `else`, since an `if` with no `else` just falls through.
+fn expr_diverges(expr: &Expr) -> bool {
+    match expr.node {
+        ExprKind::Ret(_) | ExprKind::Break(..) | ExprKind::Continue(_) => true,
+        ExprKind::Mac(ref mac) => {
+            let name = mac.path.segments.last().map(|seg| seg.ident.to_string());
+            name.as_deref() == Some("panic") || name.as_deref() == Some("unreachable")
+        }
+        ExprKind::If(_, ref body, Some(ref els)) => {
+            diverges(&body.stmts)
+                && match els.node {
+                    ExprKind::Block(ref blk, _) => diverges(&blk.stmts),
+                    ExprKind::If(..) => expr_diverges(els),
+                    _ => false,
+                }
         }
+        _ => false,
+    }
+}
+
+/// `unused_braces` applied to relooper output: drop an empty unlabelled
+/// block statement (cleaning up the empty `else` arms `EmptyElse` leaves
+/// behind), and inline an unlabelled block's lone non-`let` statement into
+/// its parent. Both skip `unsafe`/`const`/`async`/`try` blocks — gated on
+/// `blk.rules == BlockCheckMode::Default` — since those are also
+/// `Block(_, None)` but dropping/inlining them would lose that context.
+/// Loop bodies aren't candidates since a loop body is a field of its
+/// `Loop`/`While`/`ForLoop` expr, never a standalone statement here.
+struct UnusedBraces;
+
+impl StmtSimplify for UnusedBraces {
+    fn apply(&self, stmts: &mut Vec<Stmt>) -> bool {
+        flatten_unused_braces(stmts)
     }
-    stmt
-}
\ No newline at end of file
+}
+
+fn flatten_unused_braces(stmts: &mut Vec<Stmt>) -> bool {
+    let mut changed = false;
+    let mut i = 0;
+    while i < stmts.len() {
+        if let StmtKind::Expr(ref mut expr) | StmtKind::Semi(ref mut expr) = stmts[i].node {
+            match expr.node {
+                ExprKind::If(_, ref mut body, ref mut els) => {
+                    changed |= flatten_unused_braces(&mut body.stmts);
+                    if let Some(ref mut els) = els {
+                        if let ExprKind::Block(ref mut blk, None) = els.node {
+                            changed |= flatten_unused_braces(&mut blk.stmts);
+                        }
+                    }
+                    let empty_else = match *els {
+                        Some(ref e) => match e.node {
+                            ExprKind::Block(ref blk, None) => blk.rules == BlockCheckMode::Default && blk.stmts.is_empty(),
+                            _ => false,
+                        },
+                        None => false,
+                    };
+                    if empty_else {
+                        *els = None;
+                        changed = true;
+                    }
+                }
+                ExprKind::Match(_, ref mut cases) => {
+                    for case in cases {
+                        if let ExprKind::Block(ref mut blk, None) = case.body.node {
+                            changed |= flatten_unused_braces(&mut blk.stmts);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let inlined = match stmts[i].node {
+            StmtKind::Expr(ref expr) | StmtKind::Semi(ref expr) => match expr.node {
+                ExprKind::Block(ref blk, None)
+                    if blk.rules == BlockCheckMode::Default && blk.stmts.is_empty() =>
+                {
+                    Some(None)
+                }
+                ExprKind::Block(ref blk, None)
+                    if blk.rules == BlockCheckMode::Default
+                        && blk.stmts.len() == 1
+                        && !matches!(blk.stmts[0].node, StmtKind::Local(_)) =>
+                {
+                    Some(Some(blk.stmts[0].clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match inlined {
+            Some(None) => {
+                stmts.remove(i);
+                changed = true;
+            }
+            Some(Some(inner)) => {
+                stmts[i] = inner;
+                changed = true;
+            }
+            None => i += 1,
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needless_return_collapses_if_else_into_tail_exprs() {
+        // if c { return a; } else { return b; }  ->  if c { a } else { b }
+        let then_blk = mk().block(vec![mk().semi_stmt(mk().ret_expr(Some(mk().path_expr(vec!["a"]))))]);
+        let else_blk = mk().block(vec![mk().semi_stmt(mk().ret_expr(Some(mk().path_expr(vec!["b"]))))]);
+        let if_expr = mk().ifte_expr(mk().path_expr(vec!["c"]), then_blk, mk().block_expr(else_blk));
+        let mut stmts = vec![mk().expr_stmt(if_expr)];
+
+        let cleanup = IncCleanup::new(Some(ImplicitReturnType::StmtExpr(Mutability::Immutable)), Label::Synthetic(0));
+        cleanup.remove_tail_expr(&mut stmts);
+
+        match stmts[0].node {
+            StmtKind::Expr(ref expr) | StmtKind::Semi(ref expr) => match expr.node {
+                ExprKind::If(_, ref body, Some(ref els)) => {
+                    assert!(matches!(body.stmts[0].node, StmtKind::Expr(_)));
+                    match els.node {
+                        ExprKind::Block(ref blk, _) => {
+                            assert!(matches!(blk.stmts[0].node, StmtKind::Expr(_)))
+                        }
+                        _ => panic!("expected a block else-arm"),
+                    }
+                }
+                _ => panic!("expected an if expression"),
+            },
+            _ => panic!("expected an expr statement"),
+        }
+    }
+
+    #[test]
+    fn redundant_else_is_spliced_after_a_diverging_if() {
+        // if c { return; } else { rest; }  ->  if c { return; } rest;
+        let then_blk = mk().block(vec![mk().semi_stmt(mk().ret_expr(None))]);
+        let rest_stmt = mk().semi_stmt(mk().path_expr(vec!["rest"]));
+        let else_blk = mk().block(vec![rest_stmt]);
+        let if_expr = mk().ifte_expr(mk().path_expr(vec!["c"]), then_blk, mk().block_expr(else_blk));
+        let mut stmts = vec![mk().semi_stmt(if_expr)];
+
+        let changed = RedundantElse.apply(&mut stmts);
+
+        assert!(changed);
+        assert_eq!(stmts.len(), 2);
+        match stmts[0].node {
+            StmtKind::Expr(ref expr) | StmtKind::Semi(ref expr) => match expr.node {
+                ExprKind::If(_, _, ref els) => assert!(els.is_none()),
+                _ => panic!("expected an if expression"),
+            },
+            _ => panic!("expected an expr statement"),
+        }
+    }
+
+    #[test]
+    fn unsafe_block_is_not_inlined_or_dropped() {
+        let inner = mk().semi_stmt(mk().path_expr(vec!["x"]));
+        let unsafe_blk = mk().unsafe_block(vec![inner]);
+        let mut stmts = vec![mk().semi_stmt(mk().block_expr(unsafe_blk))];
+
+        let changed = UnusedBraces.apply(&mut stmts);
+
+        assert!(!changed);
+        assert_eq!(stmts.len(), 1);
+        match stmts[0].node {
+            StmtKind::Expr(ref expr) | StmtKind::Semi(ref expr) => {
+                assert!(matches!(expr.node, ExprKind::Block(..)))
+            }
+            _ => panic!("expected the unsafe block to survive untouched"),
+        }
+    }
+}